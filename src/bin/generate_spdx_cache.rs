@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use license_retriever::error::Result;
+
+fn main() -> Result<()> {
+    let tmp_dir = std::env::temp_dir().join("license-retriever-spdx-cache-gen");
+    if tmp_dir.exists() {
+        std::fs::remove_dir_all(&tmp_dir)?;
+    }
+    git2::build::RepoBuilder::new()
+        .fetch_options({
+            let mut fo = git2::FetchOptions::new();
+            fo.depth(1);
+            fo
+        })
+        .clone("https://github.com/spdx/license-list-data", &tmp_dir)?;
+
+    let mut texts = HashMap::new();
+    for entry in tmp_dir.join("text").read_dir()? {
+        let entry = entry?;
+        let Some(id) = entry
+            .path()
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        texts.insert(id, std::fs::read_to_string(entry.path())?);
+    }
+
+    let encoded = rmp_serde::to_vec_named(&texts)?;
+    let compressed = zstd::encode_all(encoded.as_slice(), 19)?;
+
+    let out_path = PathBuf::from("assets/spdx-license-cache.bin.zst");
+    std::fs::write(&out_path, compressed)?;
+    println!("Wrote {} license texts to {out_path:?}", texts.len());
+    Ok(())
+}
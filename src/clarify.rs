@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use semver::{Version, VersionReq};
+use sha2::{Digest, Sha256};
+
+use crate::error::{Error, Result};
+
+#[derive(Clone, Debug)]
+pub struct FileSource {
+    pub path: PathBuf,
+    pub sha256: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Clarification {
+    pub crate_name: String,
+    pub version_req: VersionReq,
+    pub expression: String,
+    pub sources: Vec<FileSource>,
+}
+
+impl Clarification {
+    pub fn matches(&self, name: &str, version: &Version) -> bool {
+        self.crate_name == name && self.version_req.matches(version)
+    }
+}
+
+pub fn find<'a>(
+    clarifications: &'a [Clarification],
+    name: &str,
+    version: &Version,
+) -> Option<&'a Clarification> {
+    clarifications.iter().find(|c| c.matches(name, version))
+}
+
+pub fn verify_and_read(package_root: &Path, source: &FileSource) -> Result<String> {
+    let path = package_root.join(&source.path);
+    let contents = std::fs::read_to_string(&path)?;
+    let actual = format!("{:x}", Sha256::digest(contents.as_bytes()));
+    if !actual.eq_ignore_ascii_case(&source.sha256) {
+        return Err(Error::ClarificationHashMismatch {
+            path,
+            expected: source.sha256.clone(),
+            actual,
+        });
+    }
+    Ok(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_and_read_returns_contents_when_hash_matches() {
+        let dir = std::env::temp_dir().join(format!(
+            "license-retriever-clarify-test-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("LICENSE"), "MIT License text").unwrap();
+        let source = FileSource {
+            path: PathBuf::from("LICENSE"),
+            sha256: format!("{:x}", Sha256::digest(b"MIT License text")),
+        };
+        assert_eq!(
+            verify_and_read(&dir, &source).unwrap(),
+            "MIT License text"
+        );
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn verify_and_read_errors_on_hash_mismatch() {
+        let dir = std::env::temp_dir().join(format!(
+            "license-retriever-clarify-test-mismatch-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("LICENSE"), "a drifted license text").unwrap();
+        let source = FileSource {
+            path: PathBuf::from("LICENSE"),
+            sha256: format!("{:x}", Sha256::digest(b"the original license text")),
+        };
+        let err = verify_and_read(&dir, &source).unwrap_err();
+        assert!(matches!(err, Error::ClarificationHashMismatch { .. }));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -3,10 +3,15 @@ use std::{
     path::PathBuf,
 };
 
+use crate::clarify::Clarification;
+
 #[derive(Clone, Default)]
 pub struct Config {
     pub overrides: HashMap<String, Vec<String>>,
     pub ignored_crates: HashSet<String>,
     pub manifest_path: Option<PathBuf>,
     pub error_for_no_license: bool,
+    pub license_preference: Vec<String>,
+    pub fallback_to_git_clone: bool,
+    pub clarifications: Vec<Clarification>,
 }
@@ -0,0 +1,57 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+static COPYRIGHT_LINE_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?im)^[\s*#>/-]*(copyright\s*(?:\(c\)|©)?\s*(?:\d{4}[-,\d\s]*)?\S.*)$").unwrap()
+});
+
+pub fn extract_copyright_lines(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = COPYRIGHT_LINE_RE
+        .captures_iter(text)
+        .map(|c| c[1].trim().to_owned())
+        .collect();
+    lines.sort();
+    lines.dedup();
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_and_dedupes_copyright_lines() {
+        let text = "Copyright (c) 2021 Jane Doe\nsome text\nCopyright (c) 2021 Jane Doe\nCopyright 2022-2023 John Doe";
+        assert_eq!(
+            extract_copyright_lines(text),
+            vec![
+                "Copyright (c) 2021 Jane Doe".to_owned(),
+                "Copyright 2022-2023 John Doe".to_owned(),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_lines_without_copyright() {
+        assert!(extract_copyright_lines("just some prose\nno holder here").is_empty());
+    }
+
+    #[test]
+    fn ignores_prose_that_merely_mentions_copyright() {
+        let text = "This section describes how we think about copyright law and fair use.\nAll rights reserved under applicable copyright statutes.";
+        assert!(extract_copyright_lines(text).is_empty());
+    }
+
+    #[test]
+    fn matches_bulleted_and_commented_notices() {
+        let text = "// Copyright (c) 2021 Jane Doe\n  * Copyright 2022 Acme Corp";
+        assert_eq!(
+            extract_copyright_lines(text),
+            vec![
+                "Copyright (c) 2021 Jane Doe".to_owned(),
+                "Copyright 2022 Acme Corp".to_owned(),
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -17,6 +19,18 @@ pub enum Error {
     Io(#[from] std::io::Error),
     #[error("No licenses found for: {0}")]
     NoLicensesFound(String),
+    #[error("report rendering error: {0:?}")]
+    Render(#[from] rinja::Error),
+    #[error("clarification hash mismatch for {path:?}: expected {expected}, found {actual}")]
+    ClarificationHashMismatch {
+        path: PathBuf,
+        expected: String,
+        actual: String,
+    },
+    #[error("REUSE.toml parse error: {0}")]
+    ReuseToml(#[from] toml::de::Error),
+    #[error("invalid license expression in clarification: {0}")]
+    InvalidClarificationExpression(#[from] spdx::ParseError),
     #[error("unknown error")]
     Unknown,
 }
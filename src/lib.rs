@@ -11,10 +11,51 @@ use log::{debug, info, warn};
 use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 
+use crate::copyright::extract_copyright_lines;
 use crate::error::{Error, Result};
+use crate::license_expr::LicenseExpr;
+use crate::license_match::{Confidence, LicenseMatch, LicenseTemplates};
+use crate::reuse::{scan_inline_header, ReuseInfo};
 
+pub mod clarify;
 pub mod config;
+pub mod copyright;
 pub mod error;
+pub mod license_expr;
+pub mod license_match;
+pub mod report;
+pub mod reuse;
+pub mod spdx_cache;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PackageFlag {
+    MultiplePossibleLicenseFiles,
+}
+
+/// Everything retrieved for a single package: the license texts themselves,
+/// the parsed license expression (if any), any `NOTICE`/`AUTHORS` files, and
+/// the copyright holder strings found along the way. Kept as one structured
+/// record rather than several parallel `Vec`s so the attribution output has
+/// everything it legally needs for a given package in one place.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackageLicenseRecord {
+    pub package: Package,
+    pub licenses: Vec<LicenseMatch>,
+    pub expression: Option<LicenseExpr>,
+    pub flags: Vec<PackageFlag>,
+    pub notices: Vec<String>,
+    pub copyrights: Vec<String>,
+}
+
+/// Intermediate result of a discovery step, before the owning `Package` is attached.
+#[derive(Default)]
+struct Discovered {
+    licenses: Vec<LicenseMatch>,
+    expression: Option<LicenseExpr>,
+    flags: Vec<PackageFlag>,
+    notices: Vec<String>,
+    copyrights: Vec<String>,
+}
 
 fn get_metadata(manifest_path: Option<impl Into<PathBuf>>) -> Result<Metadata> {
     info!("Retrieving metadata");
@@ -56,31 +97,148 @@ fn get_packages(metadata: &Metadata) -> Vec<&Package> {
     packages
 }
 
-fn extract_licenses_from_repo_folder(path: &Path) -> Result<Vec<String>> {
-    let mut licenses = vec![];
+fn extract_licenses_from_repo_folder(path: &Path, templates: &LicenseTemplates) -> Result<Discovered> {
+    let mut license_texts = vec![];
+    let mut notices = vec![];
     for entry in path.read_dir()? {
         let entry = entry?;
         let name = entry.file_name().to_string_lossy().to_ascii_lowercase();
-        if !name.contains("license")
-            && !name.contains("licence")
-            && !name.contains("copyright")
-            && !name.contains("copying")
-        {
+        let is_license = name.contains("license")
+            || name.contains("licence")
+            || name.contains("copyright")
+            || name.contains("copying");
+        let is_notice = name.contains("notice") || name.contains("authors");
+        if !is_license && !is_notice {
             continue;
         }
         info!("Found {:?}", entry.path());
-        if entry.file_type()?.is_dir() {
+        let contents = if entry.file_type()?.is_dir() {
+            let mut contents = vec![];
             for entry2 in entry.path().read_dir()? {
                 let entry2 = entry2?;
                 if !entry2.file_type()?.is_dir() {
-                    licenses.push(std::fs::read_to_string(entry2.path())?);
+                    contents.push(std::fs::read_to_string(entry2.path())?);
                 }
             }
+            contents
+        } else {
+            vec![std::fs::read_to_string(entry.path())?]
+        };
+        if is_license {
+            license_texts.extend(contents);
         } else {
-            licenses.push(std::fs::read_to_string(entry.path())?);
+            notices.extend(contents);
+        }
+    }
+
+    let mut flags = vec![];
+    if license_texts.iter().filter(|t| !t.trim().is_empty()).count() > 1 {
+        flags.push(PackageFlag::MultiplePossibleLicenseFiles);
+    }
+
+    let mut copyrights = vec![];
+    let licenses = license_texts
+        .into_iter()
+        .map(|text| {
+            copyrights.extend(extract_copyright_lines(&text));
+            let (spdx_id, confidence) = templates.identify(&text);
+            LicenseMatch {
+                text,
+                spdx_id,
+                confidence,
+            }
+        })
+        .collect();
+    for notice in &notices {
+        copyrights.extend(extract_copyright_lines(notice));
+    }
+    copyrights.sort();
+    copyrights.dedup();
+
+    Ok(Discovered {
+        licenses,
+        flags,
+        notices,
+        copyrights,
+        expression: None,
+    })
+}
+
+fn discover_in_folder(path: &Path, templates: &LicenseTemplates, config: &Config) -> Result<Discovered> {
+    if let Some(reuse) = ReuseInfo::load(path)? {
+        let licenses = reuse.license_matches(path, &config.license_preference)?;
+        if !licenses.is_empty() {
+            let mut copyrights: Vec<String> = reuse
+                .annotations
+                .iter()
+                .flat_map(|a| a.copyrights.iter().cloned())
+                .collect();
+            for license in &licenses {
+                copyrights.extend(extract_copyright_lines(&license.text));
+            }
+            copyrights.sort();
+            copyrights.dedup();
+            return Ok(Discovered {
+                licenses,
+                copyrights,
+                ..Default::default()
+            });
+        }
+    } else if let Some(discovered) = discover_inline_headers(path)? {
+        if !discovered.licenses.is_empty() {
+            return Ok(discovered);
+        }
+    }
+    extract_licenses_from_repo_folder(path, templates)
+}
+
+fn discover_inline_headers(path: &Path) -> Result<Option<Discovered>> {
+    let mut ids = vec![];
+    let mut copyrights = vec![];
+    scan_dir_for_inline_headers(path, &mut ids, &mut copyrights)?;
+    ids.sort();
+    ids.dedup();
+    copyrights.sort();
+    copyrights.dedup();
+    if ids.is_empty() {
+        return Ok(None);
+    }
+    let licenses = ids
+        .into_iter()
+        .filter_map(|id| {
+            spdx_cache::get(&id).map(|text| LicenseMatch {
+                text: text.to_owned(),
+                spdx_id: Some(id),
+                confidence: Confidence::Confident,
+            })
+        })
+        .collect();
+    Ok(Some(Discovered {
+        licenses,
+        copyrights,
+        ..Default::default()
+    }))
+}
+
+fn scan_dir_for_inline_headers(
+    dir: &Path,
+    ids: &mut Vec<String>,
+    copyrights: &mut Vec<String>,
+) -> Result<()> {
+    for entry in dir.read_dir()? {
+        let entry = entry?;
+        if matches!(entry.file_name().to_str(), Some("target" | ".git" | "node_modules")) {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            scan_dir_for_inline_headers(&entry.path(), ids, copyrights)?;
+        } else if let Ok(contents) = std::fs::read_to_string(entry.path()) {
+            let (file_ids, file_copyrights) = scan_inline_header(&contents);
+            ids.extend(file_ids);
+            copyrights.extend(file_copyrights);
         }
     }
-    Ok(licenses)
+    Ok(())
 }
 
 fn clone_repo(id: &str, repository: &str) -> Result<bool> {
@@ -116,13 +274,81 @@ fn clone_repo(id: &str, repository: &str) -> Result<bool> {
     }
 }
 
-fn get_licenses(package: &Package) -> Result<Vec<String>> {
+fn get_licenses(package: &Package, config: &Config, templates: &LicenseTemplates) -> Result<Discovered> {
+    if let Some(clarification) = clarify::find(&config.clarifications, &package.name, &package.version) {
+        info!(
+            "Using clarification for {} {}",
+            package.name, package.version
+        );
+        let package_root = package
+            .manifest_path
+            .parent()
+            .unwrap_or(&package.manifest_path);
+        let mut licenses = vec![];
+        for source in &clarification.sources {
+            let text = clarify::verify_and_read(package_root.as_std_path(), source)?;
+            let (spdx_id, confidence) = templates.identify(&text);
+            licenses.push(LicenseMatch {
+                text,
+                spdx_id,
+                confidence,
+            });
+        }
+        let copyrights = licenses
+            .iter()
+            .flat_map(|l| extract_copyright_lines(&l.text))
+            .collect();
+        return Ok(Discovered {
+            licenses,
+            expression: Some(LicenseExpr::parse(&clarification.expression)?),
+            copyrights,
+            ..Default::default()
+        });
+    }
+
+    // Checked after clarifications (hash-verified, so they win ties) but
+    // before auto-discovery: an override is an explicit but unverified claim.
+    if let Some(licenses) = config.overrides.get(&package.name) {
+        info!("Using override for {}", package.name);
+        let licenses: Vec<LicenseMatch> = licenses
+            .iter()
+            .map(|text| {
+                let (spdx_id, confidence) = templates.identify(text);
+                LicenseMatch {
+                    text: text.to_owned(),
+                    spdx_id,
+                    confidence,
+                }
+            })
+            .collect();
+        let copyrights = licenses
+            .iter()
+            .flat_map(|l| extract_copyright_lines(&l.text))
+            .collect();
+        return Ok(Discovered {
+            licenses,
+            copyrights,
+            ..Default::default()
+        });
+    }
+
     if let Some(license_file) = package.license_file() {
         info!(
             "Retrieving license file at {license_file:?} for {}",
             package.name
         );
-        return Ok(vec![std::fs::read_to_string(&license_file)?]);
+        let text = std::fs::read_to_string(&license_file)?;
+        let (spdx_id, confidence) = templates.identify(&text);
+        let copyrights = extract_copyright_lines(&text);
+        return Ok(Discovered {
+            licenses: vec![LicenseMatch {
+                text,
+                spdx_id,
+                confidence,
+            }],
+            copyrights,
+            ..Default::default()
+        });
     };
 
     let path = package
@@ -130,9 +356,9 @@ fn get_licenses(package: &Package) -> Result<Vec<String>> {
         .parent()
         .unwrap_or(&package.manifest_path);
     if path.exists() {
-        let licenses = extract_licenses_from_repo_folder(path.as_std_path())?;
-        if !licenses.is_empty() {
-            return Ok(licenses);
+        let discovered = discover_in_folder(path.as_std_path(), templates, config)?;
+        if !discovered.licenses.is_empty() {
+            return Ok(discovered);
         }
     }
 
@@ -151,9 +377,9 @@ fn get_licenses(package: &Package) -> Result<Vec<String>> {
             ];
             for path in paths {
                 if path.exists() {
-                    let licenses = extract_licenses_from_repo_folder(&path)?;
-                    if !licenses.is_empty() {
-                        return Ok(licenses);
+                    let discovered = discover_in_folder(&path, templates, config)?;
+                    if !discovered.licenses.is_empty() {
+                        return Ok(discovered);
                     }
                 }
             }
@@ -161,55 +387,87 @@ fn get_licenses(package: &Package) -> Result<Vec<String>> {
     }
 
     if let Some(license) = &package.license {
-        let path = PathBuf::from(format!("{}/repo/@spdx", std::env::var("OUT_DIR")?));
-        println!("{path:?}");
+        let expr = match LicenseExpr::parse(license) {
+            Ok(expr) => expr,
+            Err(e) => {
+                warn!(
+                    "Failed to parse license expression {license:?} for {}: {e}",
+                    package.name
+                );
+                return Ok(Discovered::default());
+            }
+        };
+
         let mut licenses = vec![];
-        for license in license
-            .replace(" AND ", " ")
-            .replace(" OR ", " ")
-            .replace(" WITH ", " ")
-            .replace(['(', ')'], "")
-            .replace('/', " ")
-            .split(' ')
-        {
-            let path2 = path.join("text").join(format!("{license}.txt"));
-            if path2.exists() {
-                info!("Found {path2:?}");
-                licenses.push(std::fs::read_to_string(path2)?);
+        for id in expr.ids_to_retrieve(&config.license_preference) {
+            if let Some(text) = spdx_cache::get(&id) {
+                info!("Found {id} in bundled SPDX cache");
+                licenses.push(LicenseMatch {
+                    text: text.to_owned(),
+                    spdx_id: Some(id),
+                    confidence: Confidence::Confident,
+                });
+                continue;
+            }
+            if config.fallback_to_git_clone {
+                clone_repo("@spdx", "https://github.com/spdx/license-list-data")?;
+                let path = PathBuf::from(format!(
+                    "{}/repo/@spdx/text/{id}.txt",
+                    std::env::var("OUT_DIR")?
+                ));
+                if path.exists() {
+                    info!("Found {path:?} via git fallback");
+                    licenses.push(LicenseMatch {
+                        text: std::fs::read_to_string(path)?,
+                        spdx_id: Some(id),
+                        confidence: Confidence::Confident,
+                    });
+                }
             }
         }
-        if !licenses.is_empty() {
-            return Ok(licenses);
-        }
+        let copyrights = licenses
+            .iter()
+            .flat_map(|l| extract_copyright_lines(&l.text))
+            .collect();
+        return Ok(Discovered {
+            licenses,
+            expression: Some(expr),
+            copyrights,
+            ..Default::default()
+        });
     }
 
-    Ok(vec![])
+    Ok(Discovered::default())
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default, Serialize, Deserialize)]
-pub struct LicenseRetriever(Vec<(Package, Vec<String>)>);
+pub struct LicenseRetriever(Vec<PackageLicenseRecord>);
 impl LicenseRetriever {
     pub fn from_config(config: &Config) -> Result<Self> {
         let metadata = get_metadata(config.manifest_path.as_ref())?;
         let packages = get_packages(&metadata);
 
-        info!("Cloning spdx license repo");
-        clone_repo("@spdx", "https://github.com/spdx/license-list-data")?;
+        let templates = LicenseTemplates::from_embedded_cache();
 
-        let licenses = packages
+        let records = packages
             .into_par_iter()
             .map(|a| {
-                if let Some(licenses) = config.overrides.get(&a.name) {
-                    return Ok((a.to_owned(), licenses.to_owned()));
-                }
-                Ok((a.to_owned(), get_licenses(a)?))
+                let discovered = get_licenses(a, config, &templates)?;
+                Ok(PackageLicenseRecord {
+                    package: a.to_owned(),
+                    licenses: discovered.licenses,
+                    expression: discovered.expression,
+                    flags: discovered.flags,
+                    notices: discovered.notices,
+                    copyrights: discovered.copyrights,
+                })
             })
             .collect::<Result<Vec<_>>>()?;
 
-        let no_license = licenses
+        let no_license = records
             .iter()
-            .filter(|(a, b)| b.is_empty() && !config.ignored_crates.contains(&a.name))
-            .map(|(a, _)| &a.name)
+            .filter(|r| r.licenses.is_empty() && !config.ignored_crates.contains(&r.package.name))
+            .map(|r| &r.package.name)
             .join(", ");
         if !no_license.is_empty() {
             if config.error_for_no_license {
@@ -218,7 +476,7 @@ impl LicenseRetriever {
             warn!("No licenses found for: {no_license}");
         }
 
-        Ok(Self(licenses))
+        Ok(Self(records))
     }
 
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
@@ -242,7 +500,7 @@ impl LicenseRetriever {
 }
 
 impl IntoIterator for LicenseRetriever {
-    type Item = (Package, Vec<String>);
+    type Item = PackageLicenseRecord;
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
@@ -260,3 +518,54 @@ macro_rules! license_retriever_data {
         )))
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn package(name: &str) -> Package {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "license": null,
+            "license_file": null,
+            "description": null,
+            "source": null,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "categories": [],
+            "keywords": [],
+            "readme": null,
+            "repository": null,
+            "homepage": null,
+            "documentation": null,
+            "edition": "2021",
+            "links": null,
+            "default_run": null,
+            "rust_version": null,
+            "metadata": null,
+            "publish": null,
+            "authors": [],
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn override_license_extracts_copyrights() {
+        let mut config = Config::default();
+        config.overrides.insert(
+            "alice".to_owned(),
+            vec!["MIT License\n\nCopyright (c) 2024 Alice".to_owned()],
+        );
+        let templates = LicenseTemplates::from_embedded_cache();
+        let discovered = get_licenses(&package("alice"), &config, &templates).unwrap();
+        assert_eq!(discovered.licenses.len(), 1);
+        assert_eq!(
+            discovered.copyrights,
+            vec!["Copyright (c) 2024 Alice".to_owned()]
+        );
+    }
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use spdx::{
+    expression::{ExprNode, Operator},
+    Expression, LicenseItem, ParseError, ParseMode,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LicenseExpr {
+    License(String),
+    With { license: Box<LicenseExpr>, exception: String },
+    And(Vec<LicenseExpr>),
+    Or(Vec<LicenseExpr>),
+}
+
+impl LicenseExpr {
+    pub fn parse(license: &str) -> Result<Self, ParseError> {
+        // LAX accepts the "MIT/Apache-2.0" slash convention common in Cargo
+        // manifests, which strict mode rejects.
+        let expression = Expression::parse_mode(license, ParseMode::LAX)?;
+        let mut stack: Vec<LicenseExpr> = Vec::new();
+        for node in expression.iter() {
+            match node {
+                ExprNode::Req(req) => {
+                    let id = match &req.req.license {
+                        LicenseItem::Spdx { id, or_later } => {
+                            format!("{}{}", id.name, if *or_later { "+" } else { "" })
+                        }
+                        LicenseItem::Other { doc_ref, lic_ref } => match doc_ref {
+                            Some(doc_ref) => format!("{doc_ref}:{lic_ref}"),
+                            None => lic_ref.clone(),
+                        },
+                    };
+                    let license = LicenseExpr::License(id);
+                    stack.push(match &req.req.exception {
+                        Some(exception) => LicenseExpr::With {
+                            license: Box::new(license),
+                            exception: exception.name.to_owned(),
+                        },
+                        None => license,
+                    });
+                }
+                ExprNode::Op(Operator::And) => {
+                    let rhs = stack.pop().unwrap_or(LicenseExpr::And(vec![]));
+                    let lhs = stack.pop().unwrap_or(LicenseExpr::And(vec![]));
+                    stack.push(LicenseExpr::And(vec![lhs, rhs]));
+                }
+                ExprNode::Op(Operator::Or) => {
+                    let rhs = stack.pop().unwrap_or(LicenseExpr::Or(vec![]));
+                    let lhs = stack.pop().unwrap_or(LicenseExpr::Or(vec![]));
+                    stack.push(LicenseExpr::Or(vec![lhs, rhs]));
+                }
+            }
+        }
+        Ok(stack.pop().unwrap_or(LicenseExpr::And(vec![])))
+    }
+
+    pub fn ids_to_retrieve(&self, preference: &[String]) -> Vec<String> {
+        match self {
+            LicenseExpr::License(id) => vec![id.clone()],
+            LicenseExpr::With { license, .. } => license.ids_to_retrieve(preference),
+            LicenseExpr::And(branches) => branches
+                .iter()
+                .flat_map(|b| b.ids_to_retrieve(preference))
+                .collect(),
+            LicenseExpr::Or(branches) => {
+                for preferred in preference {
+                    if let Some(branch) = branches.iter().find(|b| b.contains_id(preferred)) {
+                        return branch.ids_to_retrieve(preference);
+                    }
+                }
+                branches
+                    .first()
+                    .map(|b| b.ids_to_retrieve(preference))
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    pub fn exceptions(&self) -> Vec<String> {
+        match self {
+            LicenseExpr::License(_) => vec![],
+            LicenseExpr::With { exception, .. } => vec![exception.clone()],
+            LicenseExpr::And(branches) | LicenseExpr::Or(branches) => {
+                branches.iter().flat_map(LicenseExpr::exceptions).collect()
+            }
+        }
+    }
+
+    fn contains_id(&self, id: &str) -> bool {
+        match self {
+            LicenseExpr::License(this_id) => this_id == id,
+            LicenseExpr::With { license, .. } => license.contains_id(id),
+            LicenseExpr::And(branches) | LicenseExpr::Or(branches) => {
+                branches.iter().any(|b| b.contains_id(id))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_slash_dual_license_convention() {
+        let expr = LicenseExpr::parse("MIT/Apache-2.0").unwrap();
+        assert_eq!(expr.ids_to_retrieve(&[]), vec!["MIT"]);
+    }
+
+    #[test]
+    fn and_requires_every_branch() {
+        let expr = LicenseExpr::parse("MIT AND Apache-2.0").unwrap();
+        assert_eq!(expr.ids_to_retrieve(&[]), vec!["MIT", "Apache-2.0"]);
+    }
+
+    #[test]
+    fn or_falls_back_to_first_branch_with_no_preference() {
+        let expr = LicenseExpr::parse("MIT OR Apache-2.0").unwrap();
+        assert_eq!(expr.ids_to_retrieve(&[]), vec!["MIT"]);
+    }
+
+    #[test]
+    fn or_honors_preference() {
+        let expr = LicenseExpr::parse("MIT OR Apache-2.0").unwrap();
+        let preference = vec!["Apache-2.0".to_owned()];
+        assert_eq!(expr.ids_to_retrieve(&preference), vec!["Apache-2.0"]);
+    }
+
+    #[test]
+    fn preference_not_present_falls_back_to_first_branch() {
+        let expr = LicenseExpr::parse("MIT OR Apache-2.0").unwrap();
+        let preference = vec!["ISC".to_owned()];
+        assert_eq!(expr.ids_to_retrieve(&preference), vec!["MIT"]);
+    }
+}
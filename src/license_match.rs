@@ -0,0 +1,114 @@
+use std::collections::HashMap;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use crate::spdx_cache;
+
+static WORD_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\w+").unwrap());
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Confidence {
+    Confident,
+    SemiConfident,
+    Unsure,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseMatch {
+    pub text: String,
+    pub spdx_id: Option<String>,
+    pub confidence: Confidence,
+}
+
+pub struct LicenseTemplates(HashMap<String, HashMap<String, u32>>);
+
+impl LicenseTemplates {
+    pub fn from_embedded_cache() -> Self {
+        let templates = spdx_cache::ids()
+            .filter_map(|id| spdx_cache::get(id).map(|text| (id.to_owned(), word_frequencies(text))))
+            .collect();
+        Self(templates)
+    }
+
+    pub fn identify(&self, text: &str) -> (Option<String>, Confidence) {
+        let frequencies = word_frequencies(text);
+        let best = self
+            .0
+            .iter()
+            .map(|(id, template)| (id, mismatch_ratio(template, &frequencies)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        match best {
+            Some((id, ratio)) => (Some(id.clone()), confidence_for(ratio)),
+            None => (None, Confidence::Unsure),
+        }
+    }
+}
+
+fn word_frequencies(text: &str) -> HashMap<String, u32> {
+    let mut frequencies = HashMap::new();
+    for word in WORD_RE.find_iter(text) {
+        *frequencies
+            .entry(word.as_str().to_ascii_lowercase())
+            .or_insert(0) += 1;
+    }
+    frequencies
+}
+
+fn mismatch_ratio(template: &HashMap<String, u32>, text: &HashMap<String, u32>) -> f64 {
+    let total: u32 = template.values().sum();
+    if total == 0 {
+        return f64::MAX;
+    }
+    let error: u32 = template
+        .iter()
+        .map(|(word, count)| count.abs_diff(*text.get(word).unwrap_or(&0)))
+        .sum();
+    f64::from(error) / f64::from(total)
+}
+
+fn confidence_for(ratio: f64) -> Confidence {
+    if ratio < 0.10 {
+        Confidence::Confident
+    } else if ratio < 0.15 {
+        Confidence::SemiConfident
+    } else {
+        Confidence::Unsure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mismatch_ratio_is_zero_for_identical_text() {
+        let template = word_frequencies("the quick brown fox");
+        let text = word_frequencies("the quick brown fox");
+        assert_eq!(mismatch_ratio(&template, &text), 0.0);
+    }
+
+    #[test]
+    fn mismatch_ratio_counts_missing_and_extra_words() {
+        let template = word_frequencies("the quick brown fox");
+        let text = word_frequencies("the slow brown fox");
+        assert_eq!(mismatch_ratio(&template, &text), 1.0 / 4.0);
+    }
+
+    #[test]
+    fn mismatch_ratio_is_max_for_empty_template() {
+        let template = HashMap::new();
+        let text = word_frequencies("anything");
+        assert_eq!(mismatch_ratio(&template, &text), f64::MAX);
+    }
+
+    #[test]
+    fn confidence_thresholds() {
+        assert_eq!(confidence_for(0.0), Confidence::Confident);
+        assert_eq!(confidence_for(0.099), Confidence::Confident);
+        assert_eq!(confidence_for(0.10), Confidence::SemiConfident);
+        assert_eq!(confidence_for(0.149), Confidence::SemiConfident);
+        assert_eq!(confidence_for(0.15), Confidence::Unsure);
+    }
+}
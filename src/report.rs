@@ -0,0 +1,169 @@
+use rinja::Template;
+
+use crate::error::Result;
+use crate::LicenseRetriever;
+
+struct PackageSummary<'a> {
+    name: &'a str,
+    version: String,
+    repository: Option<&'a str>,
+    copyrights: &'a [String],
+    notices: &'a [String],
+}
+
+impl PackageSummary<'_> {
+    fn safe_repository_link(&self) -> Option<&str> {
+        self.repository
+            .filter(|r| r.starts_with("http://") || r.starts_with("https://"))
+    }
+}
+
+struct LicenseGroup<'a> {
+    spdx_id: Option<&'a str>,
+    text: &'a str,
+    packages: Vec<PackageSummary<'a>>,
+}
+
+#[derive(Template)]
+#[template(path = "report.html")]
+struct HtmlReport<'a> {
+    groups: Vec<LicenseGroup<'a>>,
+}
+
+#[derive(Template)]
+#[template(path = "report.md", ext = "txt")]
+struct MarkdownReport<'a> {
+    groups: Vec<LicenseGroup<'a>>,
+}
+
+impl LicenseRetriever {
+    fn license_groups(&self) -> Vec<LicenseGroup<'_>> {
+        let mut groups: Vec<LicenseGroup<'_>> = vec![];
+        for record in self.iter() {
+            for license in &record.licenses {
+                let summary = PackageSummary {
+                    name: &record.package.name,
+                    version: record.package.version.to_string(),
+                    repository: record.package.repository.as_deref(),
+                    copyrights: &record.copyrights,
+                    notices: &record.notices,
+                };
+                if let Some(group) = groups.iter_mut().find(|g| g.text == license.text) {
+                    group.packages.push(summary);
+                } else {
+                    groups.push(LicenseGroup {
+                        spdx_id: license.spdx_id.as_deref(),
+                        text: &license.text,
+                        packages: vec![summary],
+                    });
+                }
+            }
+        }
+        groups
+    }
+
+    pub fn to_markdown(&self) -> Result<String> {
+        Ok(MarkdownReport {
+            groups: self.license_groups(),
+        }
+        .render()?)
+    }
+
+    pub fn to_html(&self) -> Result<String> {
+        Ok(HtmlReport {
+            groups: self.license_groups(),
+        }
+        .render()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cargo_metadata::Package;
+
+    use crate::{LicenseRetriever, PackageLicenseRecord};
+
+    fn package(name: &str, repository: Option<&str>) -> Package {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "version": "1.0.0",
+            "id": format!("{name} 1.0.0"),
+            "license": "MIT",
+            "license_file": null,
+            "description": null,
+            "source": null,
+            "dependencies": [],
+            "targets": [],
+            "features": {},
+            "manifest_path": format!("/tmp/{name}/Cargo.toml"),
+            "categories": [],
+            "keywords": [],
+            "readme": null,
+            "repository": repository,
+            "homepage": null,
+            "documentation": null,
+            "edition": "2021",
+            "links": null,
+            "default_run": null,
+            "rust_version": null,
+            "metadata": null,
+            "publish": null,
+            "authors": [],
+        }))
+        .unwrap()
+    }
+
+    fn record(name: &str, repository: Option<&str>) -> PackageLicenseRecord {
+        PackageLicenseRecord {
+            package: package(name, repository),
+            licenses: vec![crate::license_match::LicenseMatch {
+                text: "MIT license text".to_owned(),
+                spdx_id: Some("MIT".to_owned()),
+                confidence: crate::license_match::Confidence::Confident,
+            }],
+            expression: None,
+            flags: vec![],
+            notices: vec!["This product includes software developed by Alice.".to_owned()],
+            copyrights: vec!["Copyright (c) 2024 Alice".to_owned()],
+        }
+    }
+
+    #[test]
+    fn to_markdown_lists_package_and_license_text() {
+        let retriever = LicenseRetriever(vec![record("alice", Some("https://example.com/alice"))]);
+        let markdown = retriever.to_markdown().unwrap();
+        assert!(markdown.contains("alice 1.0.0"));
+        assert!(markdown.contains("MIT license text"));
+        assert!(markdown.contains("Copyright (c) 2024 Alice"));
+        assert!(markdown.contains("This product includes software developed by Alice."));
+    }
+
+    #[test]
+    fn to_html_includes_notices() {
+        let retriever = LicenseRetriever(vec![record("alice", Some("https://example.com/alice"))]);
+        let html = retriever.to_html().unwrap();
+        assert!(html.contains("This product includes software developed by Alice."));
+    }
+
+    #[test]
+    fn to_markdown_escapes_package_metadata() {
+        let mut record = record("mallory", Some("https://example.com/mallory"));
+        record.package.name = "<script>alert(1)</script>".to_owned();
+        record.copyrights = vec!["<img onerror=alert(1)>".to_owned()];
+        let markdown = LicenseRetriever(vec![record]).to_markdown().unwrap();
+        assert!(!markdown.contains("<script>"));
+        assert!(!markdown.contains("<img"));
+    }
+
+    #[test]
+    fn to_html_links_safe_repository_but_not_unsafe_one() {
+        let retriever = LicenseRetriever(vec![record("alice", Some("https://example.com/alice"))]);
+        let html = retriever.to_html().unwrap();
+        assert!(html.contains(r#"<a href="https://example.com/alice">"#));
+
+        let unsafe_retriever =
+            LicenseRetriever(vec![record("mallory", Some("javascript:alert(1)"))]);
+        let html = unsafe_retriever.to_html().unwrap();
+        assert!(!html.contains("<a href=\"javascript:"));
+    }
+}
@@ -0,0 +1,295 @@
+use std::path::Path;
+
+use log::warn;
+use regex::Regex;
+use serde::Deserialize;
+use std::sync::LazyLock;
+
+use crate::error::Result;
+use crate::license_expr::LicenseExpr;
+use crate::license_match::{Confidence, LicenseMatch};
+
+static SPDX_ID_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"SPDX-License-Identifier:\s*([^\r\n*/]+)").unwrap());
+static COPYRIGHT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"SPDX-FileCopyrightText:\s*([^\r\n*/]+)").unwrap()
+});
+
+#[derive(Clone, Debug)]
+pub struct ReuseAnnotation {
+    pub path_glob: String,
+    pub expression: LicenseExpr,
+    pub copyrights: Vec<String>,
+}
+
+fn parse_annotation_expression(license: &str) -> LicenseExpr {
+    LicenseExpr::parse(license).unwrap_or_else(|e| {
+        warn!("Failed to parse license expression {license:?} in REUSE annotation: {e}");
+        LicenseExpr::License(license.to_owned())
+    })
+}
+
+pub struct ReuseInfo {
+    pub annotations: Vec<ReuseAnnotation>,
+}
+
+#[derive(Deserialize)]
+struct ReuseToml {
+    #[serde(default)]
+    annotations: Vec<TomlAnnotation>,
+}
+
+#[derive(Deserialize)]
+struct TomlAnnotation {
+    path: OneOrMany,
+    #[serde(rename = "SPDX-FileCopyrightText", default)]
+    copyrights: OneOrMany,
+    #[serde(rename = "SPDX-License-Identifier")]
+    license: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(untagged)]
+enum OneOrMany {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            OneOrMany::None => vec![],
+            OneOrMany::One(s) => vec![s],
+            OneOrMany::Many(v) => v,
+        }
+    }
+}
+
+impl ReuseInfo {
+    pub fn load(root: &Path) -> Result<Option<Self>> {
+        let toml_path = root.join("REUSE.toml");
+        if toml_path.exists() {
+            let contents = std::fs::read_to_string(&toml_path)?;
+            return Ok(match Self::from_toml(&contents) {
+                Ok(reuse) => Some(reuse),
+                Err(e) => {
+                    warn!("Failed to parse {toml_path:?}, ignoring it: {e}");
+                    None
+                }
+            });
+        }
+        let dep5_path = root.join(".reuse").join("dep5");
+        if dep5_path.exists() {
+            return Ok(Some(Self::from_dep5(&std::fs::read_to_string(dep5_path)?)));
+        }
+        Ok(None)
+    }
+
+    fn from_toml(contents: &str) -> Result<Self> {
+        let parsed: ReuseToml = toml::from_str(contents)?;
+        let annotations = parsed
+            .annotations
+            .into_iter()
+            .flat_map(|a| {
+                let expression = parse_annotation_expression(&a.license);
+                let copyrights = a.copyrights.into_vec();
+                a.path.into_vec().into_iter().map(move |path_glob| ReuseAnnotation {
+                    path_glob,
+                    expression: expression.clone(),
+                    copyrights: copyrights.clone(),
+                })
+            })
+            .collect();
+        Ok(Self { annotations })
+    }
+
+    fn from_dep5(contents: &str) -> Self {
+        let mut annotations = vec![];
+        for stanza in contents.split("\n\n") {
+            let mut path_glob = None;
+            let mut copyrights: Vec<String> = vec![];
+            let mut license = None;
+            let mut current_key = "";
+            for line in stanza.lines() {
+                if let Some(rest) = line.strip_prefix([' ', '\t']) {
+                    let rest = rest.strip_prefix('.').unwrap_or(rest).trim();
+                    match current_key {
+                        "Files" => {
+                            if let Some(path_glob) = &mut path_glob {
+                                *path_glob = format!("{path_glob} {rest}");
+                            }
+                        }
+                        "Copyright" => {
+                            if let Some(last) = copyrights.last_mut() {
+                                *last = format!("{last}\n{rest}");
+                            }
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+                let Some((key, value)) = line.split_once(':') else {
+                    continue;
+                };
+                current_key = key.trim();
+                let value = value.trim();
+                match current_key {
+                    "Files" => path_glob = Some(value.to_owned()),
+                    "Copyright" => copyrights.push(value.to_owned()),
+                    "License" => license = Some(value.to_owned()),
+                    _ => {}
+                }
+            }
+            if let Some(path_glob) = path_glob {
+                annotations.push(ReuseAnnotation {
+                    path_glob,
+                    expression: parse_annotation_expression(license.as_deref().unwrap_or_default()),
+                    copyrights,
+                });
+            }
+        }
+        Self { annotations }
+    }
+
+    pub fn spdx_ids(&self, preference: &[String]) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .annotations
+            .iter()
+            .flat_map(|a| a.expression.ids_to_retrieve(preference))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    pub fn license_matches(&self, root: &Path, preference: &[String]) -> Result<Vec<LicenseMatch>> {
+        let mut matches = vec![];
+        for id in self.spdx_ids(preference) {
+            let path = root.join("LICENSES").join(format!("{id}.txt"));
+            if path.exists() {
+                matches.push(LicenseMatch {
+                    text: std::fs::read_to_string(path)?,
+                    spdx_id: Some(id),
+                    confidence: Confidence::Confident,
+                });
+            }
+        }
+        Ok(matches)
+    }
+}
+
+pub fn scan_inline_header(contents: &str) -> (Vec<String>, Vec<String>) {
+    let ids = SPDX_ID_RE
+        .captures_iter(contents)
+        .map(|c| c[1].trim().to_owned())
+        .collect();
+    let copyrights = COPYRIGHT_RE
+        .captures_iter(contents)
+        .map(|c| c[1].trim().to_owned())
+        .collect();
+    (ids, copyrights)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_toml_parses_arrays_and_and_expressions() {
+        let reuse = ReuseInfo::from_toml(
+            r#"
+            [[annotations]]
+            path = ["src/a.rs", "src/b.rs"]
+            SPDX-FileCopyrightText = ["2021 Jane Doe", "2022 John Doe"]
+            SPDX-License-Identifier = "MIT AND Apache-2.0"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(reuse.annotations.len(), 2);
+        let first = &reuse.annotations[0];
+        assert_eq!(first.path_glob, "src/a.rs");
+        assert_eq!(
+            first.expression.ids_to_retrieve(&[]),
+            vec!["MIT", "Apache-2.0"]
+        );
+        assert_eq!(
+            first.copyrights,
+            vec!["2021 Jane Doe".to_owned(), "2022 John Doe".to_owned()]
+        );
+    }
+
+    #[test]
+    fn from_toml_handles_or_expressions_with_preference() {
+        let reuse = ReuseInfo::from_toml(
+            r#"
+            [[annotations]]
+            path = "src/a.rs"
+            SPDX-License-Identifier = "MIT OR Apache-2.0"
+            "#,
+        )
+        .unwrap();
+        let preference = vec!["Apache-2.0".to_owned()];
+        assert_eq!(reuse.spdx_ids(&preference), vec!["Apache-2.0"]);
+    }
+
+    #[test]
+    fn from_toml_accepts_single_string_path_and_copyright() {
+        let reuse = ReuseInfo::from_toml(
+            r#"
+            [[annotations]]
+            path = "src/*.rs"
+            SPDX-FileCopyrightText = "2021 Jane Doe"
+            SPDX-License-Identifier = "MIT"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(reuse.annotations.len(), 1);
+        assert_eq!(reuse.annotations[0].path_glob, "src/*.rs");
+        assert_eq!(reuse.annotations[0].copyrights, vec!["2021 Jane Doe"]);
+    }
+
+    #[test]
+    fn from_toml_rejects_invalid_toml() {
+        assert!(ReuseInfo::from_toml("this is not [[ valid toml").is_err());
+    }
+
+    #[test]
+    fn from_dep5_parses_stanzas_and_continuation_lines() {
+        let reuse = ReuseInfo::from_dep5(
+            "Files: src/*\nCopyright: 2021 Jane Doe\n 2022 John Doe\nLicense: MIT\n\nFiles: docs/*\nCopyright: 2023 Acme Corp\nLicense: CC0-1.0\n",
+        );
+        assert_eq!(reuse.annotations.len(), 2);
+        assert_eq!(reuse.annotations[0].path_glob, "src/*");
+        assert_eq!(
+            reuse.annotations[0].copyrights,
+            vec!["2021 Jane Doe\n2022 John Doe".to_owned()]
+        );
+        assert_eq!(
+            reuse.annotations[1].expression.ids_to_retrieve(&[]),
+            vec!["CC0-1.0"]
+        );
+    }
+
+    #[test]
+    fn load_ignores_an_unparseable_reuse_toml_instead_of_erroring() {
+        let dir = std::env::temp_dir().join(format!(
+            "license-retriever-reuse-test-{:?}",
+            std::thread::current().id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("REUSE.toml"), "this is not [[ valid toml").unwrap();
+        assert_eq!(ReuseInfo::load(&dir).unwrap().map(|r| r.annotations.len()), None);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn scan_inline_header_finds_ids_and_copyrights() {
+        let (ids, copyrights) = scan_inline_header(
+            "// SPDX-License-Identifier: MIT\n// SPDX-FileCopyrightText: 2024 Jane Doe\n",
+        );
+        assert_eq!(ids, vec!["MIT"]);
+        assert_eq!(copyrights, vec!["2024 Jane Doe"]);
+    }
+}
@@ -0,0 +1,16 @@
+use std::{collections::HashMap, sync::LazyLock};
+
+static CACHE_BYTES: &[u8] = include_bytes!("../assets/spdx-license-cache.bin.zst");
+
+static CACHE: LazyLock<HashMap<String, String>> = LazyLock::new(|| {
+    let decompressed = zstd::decode_all(CACHE_BYTES).expect("bundled SPDX cache is valid zstd");
+    rmp_serde::from_slice(&decompressed).expect("bundled SPDX cache is valid msgpack")
+});
+
+pub fn get(id: &str) -> Option<&'static str> {
+    CACHE.get(id).map(String::as_str)
+}
+
+pub fn ids() -> impl Iterator<Item = &'static str> {
+    CACHE.keys().map(String::as_str)
+}
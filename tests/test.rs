@@ -10,7 +10,16 @@ fn test() {
         lr,
         LicenseRetriever::from_bytes(&lr.to_bytes().unwrap()).unwrap()
     );
-    for (p, l) in lr {
-        println!("{}: {} ({:?})", p.name, l.len(), p.license);
+    for record in lr {
+        println!(
+            "{}: {} licenses, {} notices, {} copyrights ({:?}, {:?}, {:?})",
+            record.package.name,
+            record.licenses.len(),
+            record.notices.len(),
+            record.copyrights.len(),
+            record.package.license,
+            record.expression,
+            record.flags
+        );
     }
 }